@@ -1,12 +1,22 @@
 //! Defines the type of expression trees.
 //!
+//! Operations which walk a whole tree use an explicit work stack rather than recursion so
+//! that deep trees do not overflow the call stack. The first few levels of the stack are
+//! held inline; deeper levels are allocated from [Global], never from the tree's own
+//! allocator.
+//!
 //! Author --- DMorgan  
-//! Last Modified --- 2025-03-16
+//! Last Modified --- 2026-10-16
 
 use alloc::alloc::{Allocator,Global};
+use core::fmt::{self,Display,Formatter};
+use core::mem::{self,ManuallyDrop};
+use core::ptr;
+use crate::work_stack::WorkStack;
 use vec_buf::Vec;
 
-struct ExprInner<Token> {
+/// The tree of an [Expr], without its allocator.
+pub struct ExprInner<Token> {
   /// `Token` at the head of the expression tree.
   _head_token: Token,
   /// sub-expressions of the expression tree.
@@ -14,9 +24,87 @@ struct ExprInner<Token> {
 }
 
 impl<Token> ExprInner<Token> {
+  /// Constructs an ExprInner from its parts.
+  ///
+  /// # Params
+  ///
+  /// _head_token --- `Token` at the head of the expression tree.  
+  /// _sub_exprs --- sub-expressions of the expression tree.  
+  ///
+  /// # Safety
+  ///
+  /// `_sub_exprs` must have been allocated by the allocator of the [Expr] which will own
+  /// the ExprInner.
   pub const unsafe fn from_parts(_head_token: Token, _sub_exprs: Vec<Self>) -> Self {
     Self{_head_token,_sub_exprs}
   }
+  /// Deconstructs the ExprInner into its head `Token` and sub-expressions.
+  pub fn into_parts(self) -> (Token,Vec<Self>) {
+    let Self{_head_token,_sub_exprs} = self;
+
+    (_head_token,_sub_exprs)
+  }
+  /// Returns the `Token` at the head of the expression tree.
+  pub const fn head_token(&self) -> &Token {
+    &self._head_token
+  }
+  /// Returns the sub-expressions of the expression tree.
+  pub fn sub_exprs(&self) -> &[Self] {
+    self._sub_exprs.as_slice()
+  }
+  /// Deep-copies the expression tree, allocating sub-expressions with `allocator`.
+  ///
+  /// Copies are built bottom-up: a node is constructed once all of its sub-expressions
+  /// have been copied.
+  fn clone_in<Alloc>(&self, allocator: &Alloc) -> Self
+    where Token: Clone, Alloc: Allocator {
+    let mut stack = WorkStack::new();
+
+    stack.push((self._sub_exprs.as_slice().iter(),self._head_token.clone(),Vec::empty()));
+    loop {
+      let Some((sub_exprs,..)) = stack.last_mut() else { unreachable!() };
+      if let Some(sub_expr) = sub_exprs.next() {
+        let head_token = sub_expr._head_token.clone();
+
+        stack.push((sub_expr._sub_exprs.as_slice().iter(),head_token,Vec::empty()));
+        continue
+      }
+
+      let Some((_,head_token,sub_exprs)) = stack.pop() else { unreachable!() };
+      let expr = unsafe { Self::from_parts(head_token,sub_exprs) };
+      let Some((..,clones)) = stack.last_mut() else { return expr };
+
+      clones.push(expr,allocator);
+    }
+  }
+  /// Moves the sub-expressions of the expression tree from buffers allocated by `from`
+  /// into buffers allocated by `to`, freeing the old buffers through `from`.
+  fn move_in<From,To>(self, from: &From, to: &To) -> Self
+    where From: Allocator, To: Allocator {
+    let (head_token,sub_exprs) = self.into_parts();
+    let mut stack = WorkStack::new();
+
+    stack.push((sub_exprs,head_token,Vec::empty()));
+    loop {
+      let Some((sub_exprs,..)) = stack.last_mut() else { unreachable!() };
+      if let Some(sub_expr) = sub_exprs.pop() {
+        let (head_token,sub_exprs) = sub_expr.into_parts();
+
+        stack.push((sub_exprs,head_token,Vec::empty()));
+        continue
+      }
+
+      let Some((sub_exprs,head_token,mut moved)) = stack.pop() else { unreachable!() };
+      sub_exprs.free(from);
+      //Sub-expressions were popped last to first.
+      moved.as_mut_slice().reverse();
+
+      let expr = unsafe { Self::from_parts(head_token,moved) };
+      let Some((..,moved)) = stack.last_mut() else { return expr };
+
+      moved.push(expr,to);
+    }
+  }
 }
 
 /// An owned expression tree.
@@ -30,9 +118,31 @@ pub struct Expr<Token,Alloc>
 
 impl<Token,Alloc> Expr<Token,Alloc>
   where Alloc: Allocator {
-  const unsafe fn from_parts(_root_expr: ExprInner<Token>, _allocator: Alloc) -> Self {
+  /// Constructs an Expr from its parts.
+  ///
+  /// # Params
+  ///
+  /// _root_expr --- root of the expression tree.  
+  /// _allocator --- [Allocator] of the expression tree.  
+  ///
+  /// # Safety
+  ///
+  /// Every sub-expression of `_root_expr` must have been allocated by `_allocator`.
+  pub const unsafe fn from_parts(_root_expr: ExprInner<Token>, _allocator: Alloc) -> Self {
     Self{_root_expr,_allocator}
   }
+  /// Deconstructs the Expr into its root and allocator.
+  ///
+  /// # Safety
+  ///
+  /// The returned allocator must outlive the returned root, whose sub-expressions are
+  /// allocated by it. The sub-expressions are only freed if both are passed back to
+  /// [Expr::from_parts]; otherwise they are leaked.
+  pub unsafe fn into_parts(self) -> (ExprInner<Token>,Alloc) {
+    let expr = ManuallyDrop::new(self);
+
+    unsafe { (ptr::read(&expr._root_expr),ptr::read(&expr._allocator)) }
+  }
   /// Constructs an Expr from a `Token`.
   ///
   /// # Params
@@ -45,6 +155,48 @@ impl<Token,Alloc> Expr<Token,Alloc>
 
     unsafe { Self::from_parts(root_expr,allocator) }
   }
+  /// Returns the `Token` at the head of the expression tree.
+  pub const fn head_token(&self) -> &Token {
+    self._root_expr.head_token()
+  }
+  /// Returns the sub-expressions directly beneath the head of the expression tree.
+  pub fn sub_exprs(&self) -> &[ExprInner<Token>] {
+    self._root_expr.sub_exprs()
+  }
+  /// Returns the [Allocator] of the expression tree.
+  pub const fn allocator(&self) -> &Alloc {
+    &self._allocator
+  }
+  /// Returns the number of sub-expressions directly beneath the head of the expression
+  /// tree.
+  pub fn sub_expr_count(&self) -> usize {
+    self._root_expr._sub_exprs.len()
+  }
+  /// Appends an expression tree as the last sub-expression of the expression tree.
+  ///
+  /// The sub-expressions of `sub_expr` are moved into buffers allocated by this tree's
+  /// allocator and its old buffers are freed through its own allocator before that is
+  /// dropped, so this takes time linear in the size of `sub_expr`.
+  ///
+  /// # Params
+  ///
+  /// sub_expr --- expression tree to append.  
+  ///
+  /// Building a tree bottom-up this way takes time quadratic in its depth; use
+  /// [Expr::root_mut] to build a tree top-down in linear time.
+  pub fn push_sub_expr(&mut self, sub_expr: Self) -> &mut Self {
+    let (sub_expr,allocator) = unsafe { sub_expr.into_parts() };
+    let sub_expr = sub_expr.move_in(&allocator,&self._allocator);
+
+    self._root_expr._sub_exprs.push(sub_expr,&self._allocator);
+    self
+  }
+  /// Returns an [ExprMut] at the root of the expression tree.
+  pub fn root_mut(&mut self) -> ExprMut<'_,Token,Alloc> {
+    let Self{_root_expr,_allocator} = self;
+
+    ExprMut{_expr: _root_expr,_allocator}
+  }
 }
 
 impl<Token> Expr<Token,Global> {
@@ -59,3 +211,147 @@ impl<Token> Expr<Token,Global> {
     Self::new_in(head_token,allocator)
   }
 }
+
+/// A mutable position in an [Expr] which allocates new sub-expressions directly in the
+/// tree's allocator.
+pub struct ExprMut<'a,Token,Alloc>
+  where Alloc: Allocator {
+  /// Expression tree at the position.
+  _expr: &'a mut ExprInner<Token>,
+  /// Allocator of the whole expression tree.
+  _allocator: &'a Alloc,
+}
+
+impl<'a,Token,Alloc> ExprMut<'a,Token,Alloc>
+  where Alloc: Allocator {
+  /// Returns the `Token` at the head of the expression tree.
+  pub const fn head_token(&self) -> &Token {
+    self._expr.head_token()
+  }
+  /// Returns the number of sub-expressions directly beneath the head of the expression
+  /// tree.
+  pub fn sub_expr_count(&self) -> usize {
+    self._expr._sub_exprs.len()
+  }
+  /// Appends a sub-expression with no sub-expressions of its own.
+  ///
+  /// # Params
+  ///
+  /// head_token --- `Token` at the head of the new sub-expression.  
+  pub fn push_token(&mut self, head_token: Token) -> &mut Self {
+    let sub_expr = unsafe { ExprInner::from_parts(head_token,Vec::empty()) };
+
+    self._expr._sub_exprs.push(sub_expr,self._allocator);
+    self
+  }
+  /// Returns an ExprMut at a sub-expression, or `None` if `index` is out of bounds.
+  ///
+  /// # Params
+  ///
+  /// index --- index of the sub-expression.  
+  pub fn sub_expr_mut(&mut self, index: usize) -> Option<ExprMut<'_,Token,Alloc>> {
+    let _expr = self._expr._sub_exprs.as_mut_slice().get_mut(index)?;
+
+    Some(ExprMut{_expr,_allocator: self._allocator})
+  }
+  /// Moves the ExprMut to a sub-expression, or returns `None` if `index` is out of
+  /// bounds.
+  ///
+  /// # Params
+  ///
+  /// index --- index of the sub-expression.  
+  pub fn into_sub_expr(self, index: usize) -> Option<Self> {
+    let Self{_expr,_allocator} = self;
+    let _expr = _expr._sub_exprs.as_mut_slice().get_mut(index)?;
+
+    Some(Self{_expr,_allocator})
+  }
+}
+
+impl<Token,Alloc> Drop for Expr<Token,Alloc>
+  where Alloc: Allocator {
+  /// Drops the `Token`s of the expression tree and frees its sub-expressions through its
+  /// allocator.
+  fn drop(&mut self) {
+    let mut stack = WorkStack::new();
+
+    stack.push(mem::replace(&mut self._root_expr._sub_exprs,Vec::empty()));
+    while let Some(sub_exprs) = stack.last_mut() {
+      match sub_exprs.pop() {
+        Some(sub_expr) => stack.push(sub_expr.into_parts().1),
+        None => if let Some(sub_exprs) = stack.pop() { sub_exprs.free(&self._allocator) },
+      }
+    }
+  }
+}
+
+impl<Token,Alloc> Clone for Expr<Token,Alloc>
+  where Token: Clone, Alloc: Allocator + Clone {
+  fn clone(&self) -> Self {
+    let allocator = self._allocator.clone();
+    let root_expr = self._root_expr.clone_in(&allocator);
+
+    unsafe { Self::from_parts(root_expr,allocator) }
+  }
+}
+
+impl<Token1,Token2,Alloc1,Alloc2> PartialEq<Expr<Token2,Alloc2>> for Expr<Token1,Alloc1>
+  where Token1: PartialEq<Token2>, Alloc1: Allocator, Alloc2: Allocator {
+  /// Compares the expression trees structurally, ignoring their allocators.
+  fn eq(&self, other: &Expr<Token2,Alloc2>) -> bool {
+    let (lhs,rhs) = (&self._root_expr,&other._root_expr);
+    if lhs._head_token != rhs._head_token { return false }
+    if lhs._sub_exprs.len() != rhs._sub_exprs.len() { return false }
+
+    let mut stack = WorkStack::new();
+    stack.push(lhs._sub_exprs.as_slice().iter().zip(rhs._sub_exprs.as_slice()));
+    while let Some(sub_exprs) = stack.last_mut() {
+      let Some((lhs,rhs)) = sub_exprs.next() else { stack.pop(); continue };
+      if lhs._head_token != rhs._head_token { return false }
+      if lhs._sub_exprs.len() != rhs._sub_exprs.len() { return false }
+
+      stack.push(lhs._sub_exprs.as_slice().iter().zip(rhs._sub_exprs.as_slice()));
+    }
+
+    true
+  }
+}
+
+impl<Token,Alloc> Eq for Expr<Token,Alloc>
+  where Token: Eq, Alloc: Allocator {}
+
+impl<Token,Alloc> Display for Expr<Token,Alloc>
+  where Token: Display, Alloc: Allocator {
+  /// Formats the expression tree as `head [sub_expr, sub_expr]`.
+  fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+    let root_expr = &self._root_expr;
+    let mut stack = WorkStack::new();
+
+    root_expr._head_token.fmt(fmt)?;
+
+    let sub_exprs = root_expr._sub_exprs.as_slice();
+    if sub_exprs.is_empty() { return Ok(()) }
+
+    fmt.write_str(" [")?;
+    stack.push((sub_exprs.iter(),true));
+    while let Some((sub_exprs,is_first)) = stack.last_mut() {
+      let Some(sub_expr) = sub_exprs.next() else {
+        stack.pop();
+        fmt.write_str("]")?;
+        continue
+      };
+
+      if !*is_first { fmt.write_str(", ")? }
+      *is_first = false;
+      sub_expr._head_token.fmt(fmt)?;
+
+      let sub_exprs = sub_expr._sub_exprs.as_slice();
+      if sub_exprs.is_empty() { continue }
+
+      fmt.write_str(" [")?;
+      stack.push((sub_exprs.iter(),true));
+    }
+
+    Ok(())
+  }
+}
@@ -1,14 +1,15 @@
 //! Provides representations of expression trees.
 //!
 //! Author --- DMorgan  
-//! Last Modified --- 2025-03-16
+//! Last Modified --- 2026-10-16
 #![no_std]
 #![deny(missing_docs)]
 #![feature(allocator_api)]
 
-pub use crate::expr::Expr;
+pub use crate::expr::{Expr,ExprMut};
 
 extern crate alloc;
 extern crate vec_buf;
 
 pub mod expr;
+mod work_stack;
@@ -0,0 +1,51 @@
+//! Defines the stack used to walk expression trees without recursion.
+//!
+//! Author --- DMorgan  
+//! Last Modified --- 2026-10-16
+
+/// Number of entries a [WorkStack] holds before it allocates.
+const INLINE_CAPACITY: usize = 32;
+
+/// A stack which holds its first [INLINE_CAPACITY] entries inline and spills any deeper
+/// entries into the [Global](alloc::alloc::Global) allocator.
+///
+/// Walking a tree never allocates through the tree's own allocator, and shallow trees
+/// are walked without allocating at all.
+pub(crate) struct WorkStack<T> {
+  /// Bottom entries of the stack.
+  inline: [Option<T>; INLINE_CAPACITY],
+  /// Number of entries in `inline`.
+  inline_len: usize,
+  /// Entries above `inline`.
+  overflow: alloc::vec::Vec<T>,
+}
+
+impl<T> WorkStack<T> {
+  /// Constructs an empty WorkStack.
+  pub(crate) const fn new() -> Self {
+    Self{inline: [const { None }; INLINE_CAPACITY],inline_len: 0,overflow: alloc::vec::Vec::new()}
+  }
+  /// Pushes `value` onto the top of the stack.
+  pub(crate) fn push(&mut self, value: T) {
+    if self.inline_len < INLINE_CAPACITY {
+      self.inline[self.inline_len] = Some(value);
+      self.inline_len += 1;
+    } else {
+      self.overflow.push(value);
+    }
+  }
+  /// Returns the top of the stack.
+  pub(crate) fn last_mut(&mut self) -> Option<&mut T> {
+    if !self.overflow.is_empty() { return self.overflow.last_mut() }
+
+    self.inline_len.checked_sub(1).and_then(|index| self.inline[index].as_mut())
+  }
+  /// Removes the top of the stack.
+  pub(crate) fn pop(&mut self) -> Option<T> {
+    if let Some(value) = self.overflow.pop() { return Some(value) }
+    if self.inline_len == 0 { return None }
+
+    self.inline_len -= 1;
+    self.inline[self.inline_len].take()
+  }
+}
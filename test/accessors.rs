@@ -0,0 +1,36 @@
+#![feature(allocator_api)]
+
+extern crate expr;
+
+mod common;
+
+use std::cell::Cell;
+
+use common::Counting;
+use expr::Expr;
+
+fn main() {
+  let live = Cell::new(0);
+  let expr = common::two_level_in(Counting{id: 7, live: &live});
+
+  assert_eq!(*expr.head_token(), "a");
+  assert_eq!(expr.allocator().id, 7);
+  assert!(std::ptr::eq(expr.allocator().live, &live));
+
+  let sub_exprs = expr.sub_exprs();
+  assert_eq!(sub_exprs.len(), 2);
+  assert_eq!(*sub_exprs[0].head_token(), "b");
+  assert_eq!(*sub_exprs[0].sub_exprs()[0].head_token(), "c");
+  assert!(sub_exprs[0].sub_exprs()[0].sub_exprs().is_empty());
+  assert_eq!(*sub_exprs[1].head_token(), "d");
+
+  let (root_expr, allocator) = unsafe { expr.into_parts() };
+  assert_eq!(*root_expr.head_token(), "a");
+  assert_eq!(allocator.id, 7);
+
+  let expr = unsafe { Expr::from_parts(root_expr, allocator) };
+  assert_eq!(format!("{}", expr), "a [b [c], d]");
+
+  drop(expr);
+  assert_eq!(live.get(), 0);
+}
@@ -0,0 +1,38 @@
+#![feature(allocator_api)]
+
+extern crate expr;
+
+mod common;
+
+use std::cell::Cell;
+
+use common::Counting;
+
+fn main() {
+  let expr = common::two_level();
+  let clone = expr.clone();
+
+  assert_eq!(format!("{}", clone), "a [b [c], d]");
+  assert_eq!(clone.sub_expr_count(), 2);
+
+  let deep = common::chain(100_000, "y");
+  let clone = deep.clone();
+
+  assert_eq!(format!("{}", clone), format!("{}", deep));
+
+  //The copy allocates through the cloned allocator.
+  let live = Cell::new(0);
+  let expr = common::two_level_in(Counting{id: 3, live: &live});
+  let original_live = live.get();
+  assert!(original_live > 0);
+  let clone = expr.clone();
+
+  assert_eq!(clone.allocator().id, expr.allocator().id);
+  assert_eq!(live.get(), 2 * original_live);
+
+  drop(expr);
+  assert!(clone == common::two_level());
+
+  drop(clone);
+  assert_eq!(live.get(), 0);
+}
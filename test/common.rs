@@ -0,0 +1,64 @@
+//! Expression trees shared by the tests.
+#![allow(dead_code)]
+
+use std::alloc::{AllocError,Allocator,Global,Layout};
+use std::cell::Cell;
+use std::ptr::NonNull;
+
+use expr::Expr;
+
+/// Builds `a [b [c], d]` in `allocator`.
+pub fn two_level_in<Alloc>(allocator: Alloc) -> Expr<&'static str,Alloc>
+  where Alloc: Allocator + Clone {
+  let mut expr = Expr::new_in("a", allocator.clone());
+  let mut sub_expr = Expr::new_in("b", allocator.clone());
+  sub_expr.push_sub_expr(Expr::new_in("c", allocator.clone()));
+  expr.push_sub_expr(sub_expr)
+    .push_sub_expr(Expr::new_in("d", allocator));
+
+  expr
+}
+
+/// Builds `a [b [c], d]`.
+pub fn two_level() -> Expr<&'static str,Global> {
+  two_level_in(Global)
+}
+
+/// Builds a chain of `depth` unary `x` nodes ending in a `leaf` node.
+///
+/// The chain is built top-down through [ExprMut](expr::ExprMut), so this takes time
+/// linear in `depth`.
+pub fn chain(depth: usize, leaf: &'static str) -> Expr<&'static str,Global> {
+  if depth == 0 { return Expr::new(leaf) }
+
+  let mut expr = Expr::new("x");
+  let mut expr_mut = expr.root_mut();
+  for level in 1..=depth {
+    expr_mut.push_token(if level == depth { leaf } else { "x" });
+    expr_mut = expr_mut.into_sub_expr(0).unwrap();
+  }
+
+  expr
+}
+
+/// An [Allocator] which counts its live allocations.
+#[derive(Clone,Copy)]
+pub struct Counting<'a> {
+  /// Identifies the allocator.
+  pub id: usize,
+  /// Number of live allocations.
+  pub live: &'a Cell<usize>,
+}
+
+unsafe impl Allocator for Counting<'_> {
+  fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>,AllocError> {
+    let ptr = Global.allocate(layout)?;
+
+    self.live.set(self.live.get() + 1);
+    Ok(ptr)
+  }
+  unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+    self.live.set(self.live.get() - 1);
+    unsafe { Global.deallocate(ptr, layout) }
+  }
+}
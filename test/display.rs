@@ -0,0 +1,17 @@
+#![feature(allocator_api)]
+
+extern crate expr;
+
+mod common;
+
+use expr::Expr;
+
+fn main() {
+  assert_eq!(format!("{}", Expr::new("a")), "a");
+  assert_eq!(format!("{}", common::two_level()), "a [b [c], d]");
+
+  let display = format!("{}", common::chain(100_000, "y"));
+  assert_eq!(display.len(), 4 * 100_000 + 1);
+  assert!(display.starts_with("x [x ["));
+  assert!(display.trim_end_matches(']').ends_with("x [y"));
+}
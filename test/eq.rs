@@ -0,0 +1,28 @@
+#![feature(allocator_api)]
+
+extern crate expr;
+
+mod common;
+
+use std::alloc::Global;
+
+use expr::Expr;
+
+fn main() {
+  assert!(common::two_level() == common::two_level());
+  assert!(Expr::new("a") == Expr::new(String::from("a")));
+  //Differing allocators.
+  assert!(Expr::new("a") == Expr::new_in("a", &Global));
+  assert!(common::two_level() == common::two_level_in(&Global));
+  //Differing tokens.
+  assert!(Expr::new("a") != Expr::new("b"));
+  assert!(common::chain(3, "y") != common::chain(3, "z"));
+  //Differing arities.
+  let mut wider = common::two_level();
+  wider.push_sub_expr(Expr::new("e"));
+  assert!(common::two_level() != wider);
+  assert!(common::chain(3, "y") != common::chain(4, "y"));
+  //Deep trees.
+  assert!(common::chain(100_000, "y") == common::chain(100_000, "y"));
+  assert!(common::chain(100_000, "y") != common::chain(100_000, "z"));
+}
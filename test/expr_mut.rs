@@ -0,0 +1,33 @@
+#![feature(allocator_api)]
+
+extern crate expr;
+
+mod common;
+
+use std::cell::Cell;
+
+use common::Counting;
+use expr::Expr;
+
+fn main() {
+  let live = Cell::new(0);
+  let mut expr = Expr::new_in("a", Counting{id: 0, live: &live});
+  let mut root = expr.root_mut();
+
+  root.push_token("b").push_token("d");
+  assert_eq!(root.sub_expr_count(), 2);
+  assert!(root.sub_expr_mut(2).is_none());
+
+  let mut sub_expr = root.sub_expr_mut(0).unwrap();
+  assert_eq!(*sub_expr.head_token(), "b");
+  sub_expr.push_token("c");
+
+  assert!(expr == common::two_level());
+  assert!(live.get() > 0);
+
+  drop(expr);
+  assert_eq!(live.get(), 0);
+
+  let deep = common::chain(100_000, "y");
+  assert_eq!(format!("{}", deep).len(), 4 * 100_000 + 1);
+}
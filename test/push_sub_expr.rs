@@ -0,0 +1,36 @@
+#![feature(allocator_api)]
+
+extern crate expr;
+
+mod common;
+
+use std::cell::Cell;
+
+use common::Counting;
+use expr::Expr;
+
+fn main() {
+  let mut expr = Expr::new("a");
+  assert_eq!(expr.sub_expr_count(), 0);
+
+  expr.push_sub_expr(Expr::new("b"))
+    .push_sub_expr(Expr::new("c"));
+
+  assert_eq!(expr.sub_expr_count(), 2);
+  assert_eq!(format!("{}", expr), "a [b, c]");
+
+  //Pushing between allocators.
+  let (outer_live, inner_live) = (Cell::new(0), Cell::new(0));
+  let mut expr = Expr::new_in("e", Counting{id: 0, live: &outer_live});
+  let sub_expr = common::two_level_in(Counting{id: 1, live: &inner_live});
+  assert!(inner_live.get() > 0);
+
+  expr.push_sub_expr(sub_expr);
+
+  assert_eq!(inner_live.get(), 0);
+  assert!(outer_live.get() > 0);
+  assert_eq!(format!("{}", expr), "e [a [b [c], d]]");
+
+  drop(expr);
+  assert_eq!(outer_live.get(), 0);
+}